@@ -4,6 +4,11 @@ ct_python! {
 	print("static A: i32 = 1;")
 }
 
+// UNRESOLVED (chunk0-6): this checkout only contains this example, not the
+// `ct-python-macros` crate that expands `ct_python!`, so the requested `ct_env` dict
+// (build-env / `OUT_DIR` / crate-relative file access, surfaced via `compile_error!`)
+// has NOT been implemented anywhere in this tree. Needs the macro crate before it can be.
+
 static DIRECTIONS: [(f64, f64); 4] = ct_python! {
 	from math import sin, cos, tau
 	n = 4
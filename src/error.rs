@@ -0,0 +1,67 @@
+use pyo3::{PyErr, Python};
+
+use crate::context::py_err_to_string;
+
+// UNVERIFIED: this checkout has no `src/lib.rs` (the chunk0-6 fix commit notes the
+// crate's module wiring lives outside this tree), so whether this module is reachable
+// from the crate root as `crate::error` — via a `mod error;` / `pub use error::PyError;`
+// there — can't be confirmed from here. Wire it in once lib.rs is available.
+
+/// An error that occurred while running embedded Python code.
+///
+/// This wraps the original [`pyo3::PyErr`], together with the traceback that was printed
+/// at the time the error occurred, or, for an error detected on the Rust side (a missing
+/// variable or a failed conversion), a plain message describing it. The traceback has to be
+/// captured while still holding the GIL, so it is kept around here rather than being
+/// re-derived from the `PyErr` later.
+#[derive(Debug)]
+pub struct PyError {
+    traceback: String,
+    error: PyErr,
+}
+
+impl PyError {
+    /// Capture a [`PyErr`] as a `PyError`, rendering its traceback.
+    pub(crate) fn capture(py: Python, error: PyErr) -> Self {
+        let traceback = py_err_to_string(py, &error).unwrap_or_else(|_| error.to_string());
+        Self { traceback, error }
+    }
+
+    /// Build a `PyError` from a plain-text message, without going through the Python
+    /// exception's own `__str__`/repr.
+    ///
+    /// Use this for errors synthesized on the Rust side (e.g. a missing variable or a
+    /// failed conversion), where `message` is already the full human-readable text:
+    /// running it through [`PyErr::print`] would additionally repr-quote it, since e.g.
+    /// `KeyError.__str__` reprs its single argument.
+    pub(crate) fn with_message(error: PyErr, message: String) -> Self {
+        Self {
+            traceback: message,
+            error,
+        }
+    }
+
+    /// The original [`pyo3::PyErr`] that caused this error.
+    pub fn pyerr(&self) -> &PyErr {
+        &self.error
+    }
+
+    /// The name of the Python exception type that was raised, e.g. `"ValueError"`.
+    pub fn exception_type_name(&self) -> String {
+        Python::with_gil(|py| {
+            self.error
+                .get_type(py)
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|_| "<unknown>".to_string())
+        })
+    }
+}
+
+impl std::fmt::Display for PyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.traceback)
+    }
+}
+
+impl std::error::Error for PyError {}
@@ -0,0 +1,13 @@
+// UNVERIFIED: this checkout has no `src/lib.rs` (see the chunk0-6 fix commit for the
+// same gap with the macro crate), so whether this module is actually reachable from the
+// crate root as `crate::output` — via a `mod output;` there — can't be confirmed here.
+// Wire it in once lib.rs is available.
+
+/// The output captured by [`Context::run_capturing`](crate::Context::run_capturing).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedOutput {
+    /// Everything written to `sys.stdout` during the run.
+    pub stdout: String,
+    /// Everything written to `sys.stderr` during the run.
+    pub stderr: String,
+}